@@ -0,0 +1,178 @@
+use crate::board::{finalize_move, Game, ResetSelectedEvent};
+use crate::pieces::Piece;
+use bevy::prelude::*;
+use chess::{Board, BoardStatus, ChessMove, Color as PieceColor, MoveGen, Piece as PieceType};
+
+/// Controls which color the computer plays and how deep it searches.
+pub struct AiPlayer {
+    pub color: PieceColor,
+    pub depth: u8,
+}
+
+fn piece_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 20000,
+    }
+}
+
+/// Material balance of `board` from the perspective of the side to move.
+fn evaluate(board: &Board) -> i32 {
+    let mut score = 0;
+    for square in *board.combined() {
+        let piece = board.piece_on(square).unwrap();
+        let value = piece_value(piece);
+        if board.color_on(square).unwrap() == board.side_to_move() {
+            score += value;
+        } else {
+            score -= value;
+        }
+    }
+    score
+}
+
+const MATE_SCORE: i32 = 1_000_000;
+
+fn negamax(board: &Board, depth: u8, mut alpha: i32, beta: i32) -> i32 {
+    if depth == 0 {
+        return evaluate(board);
+    }
+
+    let mut move_gen = MoveGen::new_legal(board);
+    let mut best_score = i32::MIN;
+    let mut has_move = false;
+    for m in &mut move_gen {
+        has_move = true;
+        let child = board.make_move_new(m);
+        let score = -negamax(&child, depth - 1, -beta, -alpha);
+        if score > best_score {
+            best_score = score;
+        }
+        if best_score > alpha {
+            alpha = best_score;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    if !has_move {
+        return match board.status() {
+            BoardStatus::Checkmate => -MATE_SCORE - depth as i32,
+            _ => 0, // stalemate
+        };
+    }
+
+    best_score
+}
+
+/// Picks the best move for `board`'s side to move via negamax with
+/// alpha-beta pruning, searching to a fixed depth.
+pub fn choose_ai_move(board: &Board, depth: u8) -> Option<ChessMove> {
+    let mut best_move = None;
+    let mut best_score = i32::MIN;
+    let mut alpha = i32::MIN + 1;
+    let beta = i32::MAX;
+
+    for m in MoveGen::new_legal(board) {
+        let child = board.make_move_new(m);
+        let score = -negamax(&child, depth.saturating_sub(1), -beta, -alpha);
+        if best_move.is_none() || score > best_score {
+            best_score = score;
+            best_move = Some(m);
+        }
+        if score > alpha {
+            alpha = score;
+        }
+    }
+
+    best_move
+}
+
+fn make_ai_move(
+    mut commands: Commands,
+    ai_player: Option<Res<AiPlayer>>,
+    mut game: ResMut<Game>,
+    mut pieces_query: Query<(Entity, &mut Piece)>,
+    mut reset_selected_event: EventWriter<ResetSelectedEvent>,
+    mut audio_events: EventWriter<crate::audio::ChessAudioEvent>,
+    mut state: ResMut<State<crate::board::AppState>>,
+) {
+    let ai_player = if let Some(ai_player) = ai_player {
+        ai_player
+    } else {
+        return;
+    };
+    let board = game.chess_game.current_position();
+    if board.side_to_move() != ai_player.color {
+        return;
+    }
+
+    let chosen_move = if let Some(m) = choose_ai_move(&board, ai_player.depth) {
+        m
+    } else {
+        return;
+    };
+
+    let old_square = chosen_move.get_source();
+    let new_square = chosen_move.get_dest();
+    let piece_type = board.piece_on(old_square).unwrap();
+
+    finalize_move(
+        &mut commands,
+        &mut game,
+        &mut pieces_query,
+        &mut reset_selected_event,
+        &mut audio_events,
+        &mut state,
+        old_square,
+        new_square,
+        ai_player.color,
+        piece_type,
+        chosen_move.get_promotion(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    /// Fool's mate, one ply before Black delivers Qd8h4#.
+    const FOOLS_MATE_FEN: &str = "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2";
+
+    #[test]
+    fn choose_ai_move_finds_mate_in_one() {
+        let board = Board::from_str(FOOLS_MATE_FEN).unwrap();
+        let mv = choose_ai_move(&board, 2).unwrap();
+        let mated = board.make_move_new(mv);
+        assert_eq!(mated.status(), BoardStatus::Checkmate);
+    }
+
+    #[test]
+    fn negamax_scores_checkmate_with_depth_offset() {
+        let board = Board::from_str(FOOLS_MATE_FEN).unwrap();
+        let mate_move = choose_ai_move(&board, 2).unwrap();
+        let mated_board = board.make_move_new(mate_move);
+
+        // The side to move has no legal moves and is in checkmate, so
+        // negamax should report a loss scaled by MATE_SCORE and offset by
+        // the search depth (preferring faster mates over slower ones).
+        let score = negamax(&mated_board, 1, i32::MIN + 1, i32::MAX);
+        assert_eq!(score, -MATE_SCORE - 1);
+    }
+}
+
+pub struct AiPlugin;
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(crate::board::AppState::Playing)
+                .with_system(make_ai_move.system()),
+        );
+    }
+}
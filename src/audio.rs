@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+/// Distinct gameplay moments `finalize_move` classifies a move into, in
+/// descending priority — a move is reported as exactly one of these.
+pub enum ChessAudioEvent {
+    Checkmate,
+    Check,
+    Castle,
+    Promote,
+    Capture,
+    Move,
+}
+
+fn play_audio_events(
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    mut events: EventReader<ChessAudioEvent>,
+) {
+    for event in events.iter() {
+        let path = match event {
+            ChessAudioEvent::Checkmate => "sounds/checkmate.ogg",
+            ChessAudioEvent::Check => "sounds/check.ogg",
+            ChessAudioEvent::Castle => "sounds/castle.ogg",
+            ChessAudioEvent::Promote => "sounds/promote.ogg",
+            ChessAudioEvent::Capture => "sounds/capture.ogg",
+            ChessAudioEvent::Move => "sounds/move.ogg",
+        };
+        audio.play(asset_server.load(path));
+    }
+}
+
+pub struct ChessAudioPlugin;
+impl Plugin for ChessAudioPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<ChessAudioEvent>()
+            .add_system(play_audio_events.system());
+    }
+}
@@ -1,9 +1,11 @@
 use crate::pieces::*;
-use bevy::{app::AppExit, prelude::*};
+use bevy::prelude::*;
 use bevy_mod_picking::*;
 use chess::{
-    ChessMove, Color as PieceColor, File, Game as ChessGame, Piece as PieceType, Rank, Square,
+    ChessMove, Color as PieceColor, File, Game as ChessGame, MoveGen, Piece as PieceType, Rank,
+    Square,
 };
+use std::collections::HashSet;
 
 pub struct Game {
     pub chess_game: ChessGame,
@@ -16,6 +18,17 @@ impl Default for Game {
     }
 }
 
+/// Top-level flow: the menu, an in-progress game, and the post-game screen.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum AppState {
+    MainMenu,
+    Playing,
+    GameOver,
+}
+
+/// Which side won, set just before transitioning into `AppState::GameOver`.
+pub struct Winner(pub PieceColor);
+
 fn create_board(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -50,6 +63,7 @@ fn create_board(
 
 fn color_squares(
     selected_square: Res<SelectedSquare>,
+    legal_moves: Res<LegalMoves>,
     materials: Res<SquareMaterials>,
     mut query: Query<(Entity, &Square, &mut Handle<StandardMaterial>)>,
     picking_camera_query: Query<&PickingCamera>,
@@ -69,6 +83,8 @@ fn color_squares(
             materials.highlight_color.clone()
         } else if Some(entity) == selected_square.entity {
             materials.selected_color.clone()
+        } else if legal_moves.squares.contains(square) {
+            materials.legal_move_color.clone()
         } else if (square.get_rank().to_index() + square.get_file().to_index() + 1) % 2 == 0 {
             materials.white_color.clone()
         } else {
@@ -82,6 +98,7 @@ struct SquareMaterials {
     selected_color: Handle<StandardMaterial>,
     black_color: Handle<StandardMaterial>,
     white_color: Handle<StandardMaterial>,
+    legal_move_color: Handle<StandardMaterial>,
 }
 
 impl FromWorld for SquareMaterials {
@@ -95,6 +112,41 @@ impl FromWorld for SquareMaterials {
             selected_color: materials.add(Color::rgb(0.9, 0.1, 0.1).into()),
             black_color: materials.add(Color::rgb(0., 0.1, 0.1).into()),
             white_color: materials.add(Color::rgb(1., 0.9, 0.9).into()),
+            legal_move_color: materials.add(Color::rgba(0.2, 0.8, 0.2, 0.5).into()),
+        }
+    }
+}
+
+/// Destination squares reachable by the currently selected piece, recomputed
+/// whenever `SelectedPiece` changes.
+#[derive(Default)]
+struct LegalMoves {
+    squares: HashSet<Square>,
+}
+
+fn compute_legal_moves(
+    selected_piece: Res<SelectedPiece>,
+    game: Res<Game>,
+    pieces_query: Query<&Piece>,
+    mut legal_moves: ResMut<LegalMoves>,
+) {
+    if !selected_piece.is_changed() {
+        return;
+    }
+
+    legal_moves.squares.clear();
+
+    let piece_square = match selected_piece
+        .entity
+        .and_then(|entity| pieces_query.get(entity).ok())
+    {
+        Some(piece) => piece.square,
+        None => return,
+    };
+
+    for chess_move in MoveGen::new_legal(&game.chess_game.current_position()) {
+        if chess_move.get_source() == piece_square {
+            legal_moves.squares.insert(chess_move.get_dest());
         }
     }
 }
@@ -114,7 +166,22 @@ fn select_square(
     mut selected_piece: ResMut<SelectedPiece>,
     squares_query: Query<&Square>,
     picking_camera_query: Query<&PickingCamera>,
+    pending_promotion: Res<PendingPromotion>,
+    game: Res<Game>,
+    network_config: Option<Res<crate::net::NetworkConfig>>,
 ) {
+    // Input is paused while a promotion choice is pending
+    if pending_promotion.0.is_some() {
+        return;
+    }
+
+    // In a networked game, ignore input when it isn't our turn
+    if let Some(network_config) = network_config {
+        if game.chess_game.current_position().side_to_move() != network_config.local_color {
+            return;
+        }
+    }
+
     // Only run if the left button is pressed
     if !mouse_button_inputs.just_pressed(MouseButton::Left) {
         return;
@@ -172,6 +239,160 @@ fn select_piece(
     }
 }
 
+/// Applies an already-legal move to the `Piece` entities: relocates the
+/// moved piece, marks captures (including en passant) and castling rook
+/// hops, and flags promotions. Shared by the local player's `move_piece`
+/// and anything else that drives a move through the board (e.g. the AI).
+pub fn apply_move_to_pieces(
+    commands: &mut Commands,
+    pieces_query: &mut Query<(Entity, &mut Piece)>,
+    old_board: &chess::Board,
+    old_square: Square,
+    new_square: Square,
+    piece_color: PieceColor,
+    piece_type: PieceType,
+    promotion: Option<PieceType>,
+) {
+    for (entity, mut a_piece) in pieces_query.iter_mut() {
+        // check if it is the piece we are moving
+        if a_piece.square == old_square {
+            a_piece.square = new_square;
+            if let Some(promotion_piece) = promotion {
+                a_piece.piece_type = promotion_piece;
+                commands.entity(entity).insert(Promoted);
+            }
+        }
+        // check if piece where we moved to
+        else if a_piece.square == new_square {
+            let captured_piece = old_board.piece_on(new_square);
+            if captured_piece.is_some() {
+                // Mark the piece as taken
+                commands.entity(entity).insert(Taken);
+            }
+        }
+
+        // check for castle move
+        if a_piece.piece_type == PieceType::Rook && a_piece.color == piece_color {
+            let horizontal_movement =
+                old_square.get_file().to_index() as i8 - new_square.get_file().to_index() as i8;
+            let castles = piece_type == PieceType::King && horizontal_movement.abs() > 1;
+
+            if castles {
+                if horizontal_movement > 0 {
+                    // castle to left side of board (towards A rank)
+                    if a_piece.square.get_file() == File::A {
+                        match new_square.right() {
+                            Some(rook_square) => a_piece.square = rook_square,
+                            None => {}
+                        }
+                    }
+                } else {
+                    // castle to right side of board (towards H rank)
+                    if a_piece.square.get_file() == File::H {
+                        match new_square.left() {
+                            Some(rook_square) => a_piece.square = rook_square,
+                            None => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        // check for en passant
+        if piece_type == PieceType::Pawn
+            && old_board.en_passant() == new_square.backward(piece_color)
+            && Some(a_piece.square) == old_board.en_passant()
+        {
+            // Mark the piece as taken
+            commands.entity(entity).insert(Taken);
+        }
+    }
+}
+
+/// A pawn reached the back rank and is waiting on the player to pick the
+/// piece it promotes to; selection input is paused until it resolves.
+pub struct PendingPromotionMove {
+    pub from: Square,
+    pub to: Square,
+    pub color: PieceColor,
+}
+
+#[derive(Default)]
+pub struct PendingPromotion(pub Option<PendingPromotionMove>);
+
+/// Plays out an already-chosen move: makes it on the `ChessGame`, updates the
+/// `Piece` entities, and classifies it for `ChessAudioEvent`. Returns `false`
+/// if the move turned out to be illegal (nothing is changed in that case).
+/// Shared with anything else that drives a move through the board, such as
+/// the AI and the networking receiver.
+pub(crate) fn finalize_move(
+    commands: &mut Commands,
+    game: &mut Game,
+    pieces_query: &mut Query<(Entity, &mut Piece)>,
+    reset_selected_event: &mut EventWriter<ResetSelectedEvent>,
+    audio_events: &mut EventWriter<crate::audio::ChessAudioEvent>,
+    state: &mut State<AppState>,
+    old_square: Square,
+    new_square: Square,
+    piece_color: PieceColor,
+    piece_type: PieceType,
+    promotion: Option<PieceType>,
+) -> bool {
+    let m = ChessMove::new(old_square, new_square, promotion);
+    let old_board = game.chess_game.current_position().to_owned();
+    if !game.chess_game.make_move(m) {
+        return false;
+    }
+
+    apply_move_to_pieces(
+        commands,
+        pieces_query,
+        &old_board,
+        old_square,
+        new_square,
+        piece_color,
+        piece_type,
+        promotion,
+    );
+
+    let is_capture = old_board.piece_on(new_square).is_some()
+        || (piece_type == PieceType::Pawn
+            && old_board.en_passant() == new_square.backward(piece_color));
+    let is_castle = piece_type == PieceType::King
+        && (old_square.get_file().to_index() as i8 - new_square.get_file().to_index() as i8).abs()
+            > 1;
+    let new_position = game.chess_game.current_position();
+    let in_check = *new_position.checkers() != chess::EMPTY;
+    let is_checkmate = new_position.status() == chess::BoardStatus::Checkmate;
+
+    if is_checkmate {
+        // The side that just moved delivered mate; the side to move is mated.
+        let winner = match new_position.side_to_move() {
+            PieceColor::White => PieceColor::Black,
+            PieceColor::Black => PieceColor::White,
+        };
+        commands.insert_resource(Winner(winner));
+        let _ = state.set(AppState::GameOver);
+    }
+
+    audio_events.send(if is_checkmate {
+        crate::audio::ChessAudioEvent::Checkmate
+    } else if in_check {
+        crate::audio::ChessAudioEvent::Check
+    } else if is_castle {
+        crate::audio::ChessAudioEvent::Castle
+    } else if promotion.is_some() {
+        crate::audio::ChessAudioEvent::Promote
+    } else if is_capture {
+        crate::audio::ChessAudioEvent::Capture
+    } else {
+        crate::audio::ChessAudioEvent::Move
+    });
+
+    reset_selected_event.send(ResetSelectedEvent);
+    true
+}
+
 fn move_piece(
     mut commands: Commands,
     selected_square: Res<SelectedSquare>,
@@ -180,22 +401,18 @@ fn move_piece(
     squares_query: Query<&Square>,
     mut pieces_query: Query<(Entity, &mut Piece)>,
     mut reset_selected_event: EventWriter<ResetSelectedEvent>,
+    mut audio_events: EventWriter<crate::audio::ChessAudioEvent>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    network_link: Option<ResMut<crate::net::NetworkLink>>,
+    mut state: ResMut<State<AppState>>,
 ) {
-    let mut piece_index_opt: Option<usize> = None;
-    let mut entity_pieces: Vec<(Entity, Mut<Piece>)> = pieces_query
-        .iter_mut()
-        .enumerate()
-        .map(|(i, (entity, a_piece))| {
-            if let Some(selected_piece_entity) = selected_piece.entity {
-                if selected_piece_entity == entity {
-                    piece_index_opt = Some(i);
-                }
-            }
-            return (entity, a_piece);
-        })
-        .collect();
-    let piece_index = if let Some(piece_index) = piece_index_opt {
-        piece_index
+    // Input is paused while a promotion choice is pending
+    if pending_promotion.0.is_some() {
+        return;
+    }
+
+    let selected_piece_entity = if let Some(entity) = selected_piece.entity {
+        entity
     } else {
         return;
     };
@@ -209,102 +426,192 @@ fn move_piece(
         return;
     };
 
-    let square = if let Ok(square) = squares_query.get(square_entity) {
-        square
+    let new_square = if let Ok(square) = squares_query.get(square_entity) {
+        *square
     } else {
         return;
     };
 
-    // Move the selected piece to the selected square
-    let old_square = entity_pieces[piece_index].1.square;
-    let new_square = *square;
-    let piece_color = entity_pieces[piece_index].1.color;
-    let piece_type = entity_pieces[piece_index].1.piece_type;
-    // Check if promotion
-    let promotion: Option<PieceType> = match piece_type {
-        PieceType::Pawn => match piece_color {
-            PieceColor::Black => {
-                if new_square.get_rank() == Rank::First {
-                    Some(PieceType::Queen)
-                } else {
-                    None
-                }
-            }
-            PieceColor::White => {
-                if new_square.get_rank() == Rank::Eighth {
-                    Some(PieceType::Queen)
-                } else {
-                    None
-                }
-            }
-        },
-        _ => None,
-    };
-    let m = ChessMove::new(old_square, new_square, promotion);
-    let old_board = game.chess_game.current_position().to_owned();
-    if game.chess_game.make_move(m) {
-        for (entity, a_piece) in entity_pieces.iter_mut() {
-            {
-                // check if it is the piece we are moving
-                if a_piece.square == old_square {
-                    a_piece.square = new_square;
-                    if let Some(promotion_piece) = promotion {
-                        a_piece.piece_type = promotion_piece;
-                        commands.entity(*entity).insert(Promoted);
-                    }
-                }
-                // check if piece where we moved to
-                else if a_piece.square == new_square {
-                    let captured_piece = old_board.piece_on(new_square);
-                    if captured_piece.is_some() {
-                        // Mark the piece as taken
-                        commands.entity(*entity).insert(Taken);
-                    }
-                }
+    let (old_square, piece_color, piece_type) =
+        if let Ok((_, piece)) = pieces_query.get(selected_piece_entity) {
+            (piece.square, piece.color, piece.piece_type)
+        } else {
+            return;
+        };
 
-                // check for castle move
-                if a_piece.piece_type == PieceType::Rook && a_piece.color == piece_color {
-                    let horizontal_movement = old_square.get_file().to_index() as i8
-                        - new_square.get_file().to_index() as i8;
-                    let castles = piece_type == PieceType::King && horizontal_movement.abs() > 1;
-
-                    if castles {
-                        if horizontal_movement > 0 {
-                            // castle to left side of board (towards A rank)
-                            if a_piece.square.get_file() == File::A {
-                                match new_square.right() {
-                                    Some(rook_square) => a_piece.square = rook_square,
-                                    None => {}
-                                }
-                            }
-                        } else {
-                            // castle to right side of board (towards H rank)
-                            if a_piece.square.get_file() == File::H {
-                                match new_square.left() {
-                                    Some(rook_square) => a_piece.square = rook_square,
-                                    None => {}
-                                }
-                            }
-                        }
+    // A pawn reaching the back rank must promote; defer to the UI instead of
+    // forcing a queen.
+    let needs_promotion = piece_type == PieceType::Pawn
+        && match piece_color {
+            PieceColor::Black => new_square.get_rank() == Rank::First,
+            PieceColor::White => new_square.get_rank() == Rank::Eighth,
+        };
+    if needs_promotion {
+        pending_promotion.0 = Some(PendingPromotionMove {
+            from: old_square,
+            to: new_square,
+            color: piece_color,
+        });
+        reset_selected_event.send(ResetSelectedEvent);
+        return;
+    }
+
+    let moved = finalize_move(
+        &mut commands,
+        &mut game,
+        &mut pieces_query,
+        &mut reset_selected_event,
+        &mut audio_events,
+        &mut state,
+        old_square,
+        new_square,
+        piece_color,
+        piece_type,
+        None,
+    );
+
+    if moved {
+        send_net_move(network_link, old_square, new_square, None);
+    }
+}
+
+/// Forwards a locally-applied move to the other client, if this is a
+/// networked game.
+fn send_net_move(
+    network_link: Option<ResMut<crate::net::NetworkLink>>,
+    from: Square,
+    to: Square,
+    promotion: Option<PieceType>,
+) {
+    if let Some(mut link) = network_link {
+        link.send(crate::net::NetMove {
+            from,
+            to,
+            promotion,
+        });
+    }
+}
+
+struct PromotionUi;
+struct PromotionChoice(PieceType);
+
+fn sync_promotion_ui(
+    mut commands: Commands,
+    pending_promotion: Res<PendingPromotion>,
+    existing_ui: Query<Entity, With<PromotionUi>>,
+) {
+    let has_ui = existing_ui.iter().next().is_some();
+    match (&pending_promotion.0, has_ui) {
+        (Some(_), false) => {
+            commands
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    color: Color::NONE.into(),
+                    ..Default::default()
+                })
+                .insert(PromotionUi)
+                .with_children(|parent| {
+                    for (label, piece_type) in [
+                        ("Queen", PieceType::Queen),
+                        ("Rook", PieceType::Rook),
+                        ("Bishop", PieceType::Bishop),
+                        ("Knight", PieceType::Knight),
+                    ]
+                    .iter()
+                    .copied()
+                    {
+                        parent
+                            .spawn_bundle(ButtonBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(120.), Val::Px(65.)),
+                                    margin: Rect {
+                                        left: Val::Px(5.),
+                                        right: Val::Px(5.),
+                                        ..Default::default()
+                                    },
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..Default::default()
+                                },
+                                color: Color::rgb(0.15, 0.15, 0.15).into(),
+                                ..Default::default()
+                            })
+                            .insert(PromotionChoice(piece_type))
+                            .with_children(|parent| {
+                                parent.spawn_bundle(TextBundle {
+                                    text: Text::with_section(
+                                        label,
+                                        TextStyle {
+                                            font_size: 25.,
+                                            color: Color::WHITE,
+                                            ..Default::default()
+                                        },
+                                        Default::default(),
+                                    ),
+                                    ..Default::default()
+                                });
+                            });
                     }
-                }
+                });
+        }
+        (None, true) => {
+            for entity in existing_ui.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        _ => {}
+    }
+}
 
-                // check for en passant
-                if piece_type == PieceType::Pawn
-                    && old_board.en_passant() == new_square.backward(piece_color)
-                    && Some(a_piece.square) == old_board.en_passant()
-                {
-                    // Mark the piece as taken
-                    commands.entity(*entity).insert(Taken);
-                }
+fn promotion_choice_input(
+    mut commands: Commands,
+    mut game: ResMut<Game>,
+    mut pieces_query: Query<(Entity, &mut Piece)>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    mut reset_selected_event: EventWriter<ResetSelectedEvent>,
+    mut audio_events: EventWriter<crate::audio::ChessAudioEvent>,
+    interaction_query: Query<(&Interaction, &PromotionChoice), Changed<Interaction>>,
+    network_link: Option<ResMut<crate::net::NetworkLink>>,
+    mut state: ResMut<State<AppState>>,
+) {
+    let pending = if let Some(pending) = pending_promotion.0.take() {
+        pending
+    } else {
+        return;
+    };
+
+    for (interaction, choice) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            let moved = finalize_move(
+                &mut commands,
+                &mut game,
+                &mut pieces_query,
+                &mut reset_selected_event,
+                &mut audio_events,
+                &mut state,
+                pending.from,
+                pending.to,
+                pending.color,
+                PieceType::Pawn,
+                Some(choice.0),
+            );
+            if moved {
+                send_net_move(network_link, pending.from, pending.to, Some(choice.0));
             }
+            return;
         }
     }
 
-    reset_selected_event.send(ResetSelectedEvent);
+    // No click yet this frame, keep waiting.
+    pending_promotion.0 = Some(pending);
 }
 
-struct ResetSelectedEvent;
+pub(crate) struct ResetSelectedEvent;
 
 fn reset_selected(
     mut event_reader: EventReader<ResetSelectedEvent>,
@@ -317,23 +624,64 @@ fn reset_selected(
     }
 }
 
+/// Fired when the player presses R; restarts the current game in place
+/// instead of requiring the process to be relaunched.
+pub struct ResetGameEvent;
+
+fn send_reset_game_event(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut reset_game_events: EventWriter<ResetGameEvent>,
+) {
+    if keyboard_input.just_pressed(KeyCode::R) {
+        reset_game_events.send(ResetGameEvent);
+    }
+}
+
+fn handle_reset_game_event(
+    mut reset_game_events: EventReader<ResetGameEvent>,
+    mut state: ResMut<State<AppState>>,
+) {
+    for _event in reset_game_events.iter() {
+        // Re-running on_exit/on_enter for Playing tears down the old board
+        // and pieces and spawns a fresh starting position.
+        state.overwrite(AppState::Playing).unwrap();
+    }
+}
+
+fn clear_selection(
+    mut commands: Commands,
+    mut selected_square: ResMut<SelectedSquare>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    promotion_ui_query: Query<Entity, With<PromotionUi>>,
+) {
+    selected_square.entity = None;
+    selected_piece.entity = None;
+
+    // A pending promotion choice (and its UI) can outlive a reset triggered
+    // mid-choice; select_square/move_piece hard-gate on pending_promotion
+    // being None, so leaving it set would permanently lock out input.
+    pending_promotion.0 = None;
+    for entity in promotion_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 struct Taken;
 fn despawn_taken_pieces(
     mut commands: Commands,
-    mut app_exit_events: EventWriter<AppExit>,
+    mut state: ResMut<State<AppState>>,
     query: Query<(Entity, &Piece, &Taken)>,
 ) {
     for (entity, piece, _taken) in query.iter() {
-        // If the king is taken, we should exit
+        // If the king is taken, the game is over
         if piece.piece_type == PieceType::King {
-            println!(
-                "{} won! Thanks for playing!",
-                match piece.color {
-                    PieceColor::White => "Black",
-                    PieceColor::Black => "White",
-                }
-            );
-            app_exit_events.send(AppExit);
+            let winner = match piece.color {
+                PieceColor::White => PieceColor::Black,
+                PieceColor::Black => PieceColor::White,
+            };
+            commands.insert_resource(Winner(winner));
+            let _ = state.set(AppState::GameOver);
         }
 
         // Despawn piece and children
@@ -341,31 +689,314 @@ fn despawn_taken_pieces(
     }
 }
 
+struct MainMenuUi;
+struct PlayButton;
+struct PlayVsAiButton;
+struct HostButton;
+struct JoinButton;
+
+fn spawn_menu_button(parent: &mut ChildBuilder, label: &str, marker: impl Component) {
+    parent
+        .spawn_bundle(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(220.), Val::Px(65.)),
+                margin: Rect {
+                    top: Val::Px(10.),
+                    bottom: Val::Px(10.),
+                    ..Default::default()
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: Color::rgb(0.15, 0.15, 0.15).into(),
+            ..Default::default()
+        })
+        .insert(marker)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    label,
+                    TextStyle {
+                        font_size: 30.,
+                        color: Color::WHITE,
+                        ..Default::default()
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+        });
+}
+
+fn setup_main_menu(mut commands: Commands) {
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(MainMenuUi);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(MainMenuUi)
+        .with_children(|parent| {
+            spawn_menu_button(parent, "Two player", PlayButton);
+            spawn_menu_button(parent, "vs AI", PlayVsAiButton);
+            spawn_menu_button(parent, "Host (LAN)", HostButton);
+            spawn_menu_button(parent, "Join (LAN)", JoinButton);
+        });
+}
+
+/// Default port both sides listen/connect on. `0.0.0.0` is only valid as
+/// a *bind* address; connecting a `TcpStream` to it would just resolve
+/// back to the local host, so "Join (LAN)" needs the actual host's IP
+/// instead, which this UI has no text-input widget to collect. Until it
+/// does, both addresses are overridable via environment variables set
+/// before launch.
+const DEFAULT_BIND_ADDRESS: &str = "0.0.0.0:7878";
+const DEFAULT_HOST_ADDRESS: &str = "127.0.0.1:7878";
+
+fn bind_address() -> String {
+    std::env::var("CHESS_BIND_ADDRESS").unwrap_or_else(|_| DEFAULT_BIND_ADDRESS.to_owned())
+}
+
+/// The address "Join (LAN)" connects to — set `CHESS_HOST_ADDRESS` to the
+/// host's actual LAN IP and port, e.g. `192.168.1.42:7878`.
+fn host_address() -> String {
+    std::env::var("CHESS_HOST_ADDRESS").unwrap_or_else(|_| DEFAULT_HOST_ADDRESS.to_owned())
+}
+
+fn main_menu_input(
+    mut commands: Commands,
+    mut state: ResMut<State<AppState>>,
+    play_query: Query<&Interaction, (Changed<Interaction>, With<PlayButton>)>,
+    play_vs_ai_query: Query<&Interaction, (Changed<Interaction>, With<PlayVsAiButton>)>,
+    host_query: Query<&Interaction, (Changed<Interaction>, With<HostButton>)>,
+    join_query: Query<&Interaction, (Changed<Interaction>, With<JoinButton>)>,
+) {
+    for interaction in play_query.iter() {
+        if *interaction == Interaction::Clicked {
+            commands.remove_resource::<crate::ai::AiPlayer>();
+            commands.remove_resource::<crate::net::NetworkLink>();
+            commands.remove_resource::<crate::net::NetworkConfig>();
+            state.set(AppState::Playing).unwrap();
+        }
+    }
+    for interaction in play_vs_ai_query.iter() {
+        if *interaction == Interaction::Clicked {
+            commands.insert_resource(crate::ai::AiPlayer {
+                color: PieceColor::Black,
+                depth: 3,
+            });
+            state.set(AppState::Playing).unwrap();
+        }
+    }
+    for interaction in host_query.iter() {
+        if *interaction == Interaction::Clicked {
+            // host()/join() block on accept()/connect(), so the actual
+            // connection runs on a background thread; poll_pending_connection
+            // picks up the result and transitions to Playing once it lands.
+            commands.insert_resource(crate::net::PendingConnection::host(&bind_address()));
+        }
+    }
+    for interaction in join_query.iter() {
+        if *interaction == Interaction::Clicked {
+            commands.insert_resource(crate::net::PendingConnection::join(&host_address()));
+        }
+    }
+}
+
+fn teardown_main_menu(mut commands: Commands, query: Query<Entity, With<MainMenuUi>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+struct GameOverUi;
+struct PlayAgainButton;
+
+fn setup_game_over(mut commands: Commands, winner: Res<Winner>) {
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(GameOverUi);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(GameOverUi)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    format!(
+                        "{} won! Thanks for playing.",
+                        match winner.0 {
+                            PieceColor::White => "White",
+                            PieceColor::Black => "Black",
+                        }
+                    ),
+                    TextStyle {
+                        font_size: 50.,
+                        color: Color::WHITE,
+                        ..Default::default()
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(180.), Val::Px(65.)),
+                        margin: Rect {
+                            top: Val::Px(20.),
+                            ..Default::default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    color: Color::rgb(0.15, 0.15, 0.15).into(),
+                    ..Default::default()
+                })
+                .insert(PlayAgainButton)
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            "Play again",
+                            TextStyle {
+                                font_size: 30.,
+                                color: Color::WHITE,
+                                ..Default::default()
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    });
+                });
+        });
+}
+
+fn game_over_input(
+    mut state: ResMut<State<AppState>>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PlayAgainButton>)>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            state.set(AppState::Playing).unwrap();
+        }
+    }
+}
+
+fn teardown_game_over(mut commands: Commands, query: Query<Entity, With<GameOverUi>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn reset_game(mut game: ResMut<Game>) {
+    *game = Game::default();
+}
+
+fn teardown_board(
+    mut commands: Commands,
+    squares_query: Query<Entity, With<Square>>,
+    pieces_query: Query<Entity, With<Piece>>,
+) {
+    for entity in squares_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in pieces_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 pub struct BoardPlugin;
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.init_resource::<SelectedSquare>()
             .init_resource::<SelectedPiece>()
+            .init_resource::<LegalMoves>()
+            .init_resource::<PendingPromotion>()
             .init_resource::<SquareMaterials>()
             .init_resource::<Game>()
             .add_event::<ResetSelectedEvent>()
-            .add_startup_system(create_board.system())
-            .add_system(color_squares.system())
-            .add_system(select_square.system().label("select_square"))
-            .add_system(
-                // move_piece needs to run before select_piece
-                move_piece
-                    .system()
-                    .after("select_square")
-                    .before("select_piece"),
+            .add_event::<ResetGameEvent>()
+            .add_plugin(crate::audio::ChessAudioPlugin)
+            .add_plugin(crate::net::NetworkPlugin)
+            .add_plugin(crate::ai::AiPlugin)
+            .add_state(AppState::MainMenu)
+            .add_system_set(
+                SystemSet::on_enter(AppState::MainMenu).with_system(setup_main_menu.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::MainMenu).with_system(main_menu_input.system()),
             )
-            .add_system(
-                select_piece
-                    .system()
-                    .after("select_square")
-                    .label("select_piece"),
+            .add_system_set(
+                SystemSet::on_exit(AppState::MainMenu).with_system(teardown_main_menu.system()),
             )
-            .add_system(despawn_taken_pieces.system())
-            .add_system(reset_selected.system().after("select_square"));
+            .add_system_set(
+                SystemSet::on_enter(AppState::Playing)
+                    .with_system(reset_game.system())
+                    .with_system(clear_selection.system())
+                    .with_system(create_board.system())
+                    .with_system(create_pieces.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Playing)
+                    .with_system(color_squares.system().after("compute_legal_moves"))
+                    .with_system(select_square.system().label("select_square"))
+                    .with_system(
+                        // move_piece needs to run before select_piece
+                        move_piece
+                            .system()
+                            .after("select_square")
+                            .before("select_piece"),
+                    )
+                    .with_system(
+                        select_piece
+                            .system()
+                            .after("select_square")
+                            .label("select_piece"),
+                    )
+                    .with_system(
+                        compute_legal_moves
+                            .system()
+                            .after("select_piece")
+                            .label("compute_legal_moves"),
+                    )
+                    .with_system(despawn_taken_pieces.system())
+                    .with_system(reset_selected.system().after("select_square"))
+                    .with_system(send_reset_game_event.system())
+                    .with_system(handle_reset_game_event.system())
+                    .with_system(sync_promotion_ui.system())
+                    .with_system(promotion_choice_input.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Playing).with_system(teardown_board.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::GameOver).with_system(setup_game_over.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver).with_system(game_over_input.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::GameOver).with_system(teardown_game_over.system()),
+            );
     }
 }
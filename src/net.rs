@@ -0,0 +1,295 @@
+use crate::board::{finalize_move, AppState, Game, ResetSelectedEvent};
+use crate::pieces::Piece;
+use bevy::prelude::*;
+use chess::{Color as PieceColor, Piece as PieceType, Square};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// A move as sent over the wire: the squares involved plus an optional
+/// promotion piece, encoded as three bytes (source index, dest index,
+/// promotion tag).
+#[derive(Clone, Copy, Debug)]
+pub struct NetMove {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<PieceType>,
+}
+
+impl NetMove {
+    fn encode(&self) -> [u8; 3] {
+        let promotion = match self.promotion {
+            None => 0,
+            Some(PieceType::Queen) => 1,
+            Some(PieceType::Rook) => 2,
+            Some(PieceType::Bishop) => 3,
+            Some(PieceType::Knight) => 4,
+            Some(_) => 0,
+        };
+        [
+            self.from.to_index() as u8,
+            self.to.to_index() as u8,
+            promotion,
+        ]
+    }
+
+    /// Decodes a move received over the wire. Returns `None` if `bytes`
+    /// doesn't describe a valid move, e.g. a corrupted packet or a
+    /// non-conforming peer sending a square index `>= 64`.
+    fn decode(bytes: [u8; 3]) -> Option<Self> {
+        let promotion = match bytes[2] {
+            1 => Some(PieceType::Queen),
+            2 => Some(PieceType::Rook),
+            3 => Some(PieceType::Bishop),
+            4 => Some(PieceType::Knight),
+            0 => None,
+            _ => return None,
+        };
+        if bytes[0] >= 64 || bytes[1] >= 64 {
+            return None;
+        }
+        Some(NetMove {
+            from: unsafe { Square::new(bytes[0]) },
+            to: unsafe { Square::new(bytes[1]) },
+            promotion,
+        })
+    }
+}
+
+/// Which side this client plays, set by `poll_pending_connection` once a
+/// "Host" or "Join" attempt from the menu has connected.
+pub struct NetworkConfig {
+    pub local_color: PieceColor,
+}
+
+/// Byte length of an encoded `NetMove`.
+const NET_MOVE_SIZE: usize = 3;
+
+/// The open connection to the other client. The stream is set to
+/// non-blocking so `receive_net_moves` can poll it once a frame.
+pub struct NetworkLink {
+    stream: TcpStream,
+    /// Bytes read so far toward the next `NetMove` frame. A single
+    /// non-blocking read can return fewer than `NET_MOVE_SIZE` bytes, so
+    /// partial frames accumulate here across calls to `try_recv` instead
+    /// of being discarded.
+    read_buf: Vec<u8>,
+}
+
+impl NetworkLink {
+    pub fn host(address: &str) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let (stream, _) = listener.accept()?;
+        stream.set_nonblocking(true)?;
+        Ok(NetworkLink {
+            stream,
+            read_buf: Vec::with_capacity(NET_MOVE_SIZE),
+        })
+    }
+
+    pub fn join(address: &str) -> std::io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nonblocking(true)?;
+        Ok(NetworkLink {
+            stream,
+            read_buf: Vec::with_capacity(NET_MOVE_SIZE),
+        })
+    }
+
+    pub fn send(&mut self, net_move: NetMove) {
+        let _ = self.stream.write_all(&net_move.encode());
+    }
+
+    fn try_recv(&mut self) -> Option<NetMove> {
+        let mut chunk = [0u8; NET_MOVE_SIZE];
+        loop {
+            let remaining = NET_MOVE_SIZE - self.read_buf.len();
+            match self.stream.read(&mut chunk[..remaining]) {
+                Ok(0) => return None,
+                Ok(n) => self.read_buf.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return None,
+                Err(_) => return None,
+            }
+
+            if self.read_buf.len() == NET_MOVE_SIZE {
+                let mut bytes = [0u8; NET_MOVE_SIZE];
+                bytes.copy_from_slice(&self.read_buf);
+                self.read_buf.clear();
+                return NetMove::decode(bytes);
+            }
+        }
+    }
+}
+
+/// A host/join attempt in progress on a background thread. `NetworkLink::host`
+/// and `NetworkLink::join` block on `accept`/`connect` before non-blocking
+/// mode is ever set, so running them on the main thread would freeze
+/// rendering until a peer shows up; this resource lets `main_menu_input`
+/// kick the connection off and `poll_pending_connection` pick up the
+/// result once it's ready, without ever blocking a frame.
+pub struct PendingConnection {
+    local_color: PieceColor,
+    receiver: Receiver<std::io::Result<NetworkLink>>,
+}
+
+impl PendingConnection {
+    pub fn host(address: &str) -> Self {
+        Self::spawn(address, PieceColor::White, NetworkLink::host)
+    }
+
+    pub fn join(address: &str) -> Self {
+        Self::spawn(address, PieceColor::Black, NetworkLink::join)
+    }
+
+    fn spawn(
+        address: &str,
+        local_color: PieceColor,
+        connect: fn(&str) -> std::io::Result<NetworkLink>,
+    ) -> Self {
+        let address = address.to_owned();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(connect(&address));
+        });
+        PendingConnection {
+            local_color,
+            receiver,
+        }
+    }
+}
+
+/// Picks up a finished host/join attempt, if any, and installs the
+/// resulting `NetworkLink`/`NetworkConfig` so `receive_net_moves` takes
+/// over once play starts. A failed attempt (e.g. connection refused)
+/// just drops the `PendingConnection`, leaving the player on the menu.
+fn poll_pending_connection(
+    mut commands: Commands,
+    pending: Option<Res<PendingConnection>>,
+    mut state: ResMut<State<AppState>>,
+) {
+    let pending = if let Some(pending) = pending {
+        pending
+    } else {
+        return;
+    };
+
+    match pending.receiver.try_recv() {
+        Ok(Ok(link)) => {
+            let local_color = pending.local_color;
+            commands.remove_resource::<PendingConnection>();
+            commands.insert_resource(link);
+            commands.insert_resource(NetworkConfig { local_color });
+            state.set(AppState::Playing).unwrap();
+        }
+        Ok(Err(_)) => commands.remove_resource::<PendingConnection>(),
+        Err(mpsc::TryRecvError::Empty) => {}
+        Err(mpsc::TryRecvError::Disconnected) => commands.remove_resource::<PendingConnection>(),
+    }
+}
+
+/// Applies a move received from the remote client through the same
+/// entity-update path the local player's moves use.
+fn receive_net_moves(
+    mut commands: Commands,
+    mut link: ResMut<NetworkLink>,
+    network_config: Res<NetworkConfig>,
+    mut game: ResMut<Game>,
+    mut pieces_query: Query<(Entity, &mut Piece)>,
+    mut reset_selected_event: EventWriter<ResetSelectedEvent>,
+    mut audio_events: EventWriter<crate::audio::ChessAudioEvent>,
+    mut state: ResMut<State<AppState>>,
+) {
+    if game.chess_game.current_position().side_to_move() == network_config.local_color {
+        // It's our turn; nothing to receive.
+        return;
+    }
+
+    let net_move = if let Some(net_move) = link.try_recv() {
+        net_move
+    } else {
+        return;
+    };
+
+    let piece = if let Some(piece) = game.chess_game.current_position().piece_on(net_move.from) {
+        piece
+    } else {
+        return;
+    };
+    let piece_color = game.chess_game.current_position().side_to_move();
+
+    finalize_move(
+        &mut commands,
+        &mut game,
+        &mut pieces_query,
+        &mut reset_selected_event,
+        &mut audio_events,
+        &mut state,
+        net_move.from,
+        net_move.to,
+        piece_color,
+        piece,
+        net_move.promotion,
+    );
+}
+
+pub struct NetworkPlugin;
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::MainMenu).with_system(poll_pending_connection.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(crate::board::AppState::Playing).with_system(
+                receive_net_moves
+                    .system()
+                    .with_run_criteria(has_network_link.system()),
+            ),
+        );
+    }
+}
+
+fn has_network_link(link: Option<Res<NetworkLink>>) -> bevy::ecs::schedule::ShouldRun {
+    if link.is_some() {
+        bevy::ecs::schedule::ShouldRun::Yes
+    } else {
+        bevy::ecs::schedule::ShouldRun::No
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(index: u8) -> Square {
+        unsafe { Square::new(index) }
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for promotion in &[
+            None,
+            Some(PieceType::Queen),
+            Some(PieceType::Rook),
+            Some(PieceType::Bishop),
+            Some(PieceType::Knight),
+        ] {
+            let net_move = NetMove {
+                from: square(12),
+                to: square(28),
+                promotion: *promotion,
+            };
+            let decoded = NetMove::decode(net_move.encode()).unwrap();
+            assert_eq!(decoded.from, net_move.from);
+            assert_eq!(decoded.to, net_move.to);
+            assert_eq!(decoded.promotion, net_move.promotion);
+        }
+    }
+
+    #[test]
+    fn decode_rejects_out_of_range_square() {
+        assert!(NetMove::decode([64, 0, 0]).is_none());
+        assert!(NetMove::decode([0, 64, 0]).is_none());
+        assert!(NetMove::decode([255, 255, 0]).is_none());
+    }
+}